@@ -0,0 +1,51 @@
+// Copyright 2021 System76 <info@system76.com>
+// SPDX-License-Identifier: MPL-2.0
+
+//! The desktop environment of the running session.
+//!
+//! Desktop entries may restrict themselves to (or exclude themselves from)
+//! particular environments through the `OnlyShowIn=` and `NotShowIn=` keys.
+//! [`DesktopEnvironment::detect`] reads the session's `XDG_CURRENT_DESKTOP`
+//! list so those keys can be honored before an entry is shown or launched.
+
+/// The desktop environment(s) advertised by the running session.
+///
+/// Parsed from the colon-separated `XDG_CURRENT_DESKTOP` variable, whose
+/// components are matched verbatim against the `OnlyShowIn`/`NotShowIn` values
+/// as required by the spec (`GNOME`, `KDE`, `XFCE`, `LXQt`, `Cinnamon`,
+/// `MATE`, `Unity`, ...).
+#[derive(Debug, Clone, Default)]
+pub struct DesktopEnvironment {
+    identifiers: Vec<String>,
+}
+
+impl DesktopEnvironment {
+    /// Detect the current environment from `XDG_CURRENT_DESKTOP`.
+    ///
+    /// Returns an empty environment when the variable is unset, in which case
+    /// entries with `OnlyShowIn` are treated as hidden and `NotShowIn` never
+    /// matches.
+    pub fn detect() -> Self {
+        std::env::var("XDG_CURRENT_DESKTOP").map(|value| Self::from_list(&value)).unwrap_or_default()
+    }
+
+    /// Parse a colon-separated `XDG_CURRENT_DESKTOP` value, dropping empty
+    /// components.
+    pub fn from_list(value: &str) -> Self {
+        let identifiers = value.split(':').filter(|id| !id.is_empty()).map(ToString::to_string).collect();
+        Self { identifiers }
+    }
+
+    /// Whether an `OnlyShowIn`/`NotShowIn` token names this session.
+    ///
+    /// The comparison is a case-sensitive exact match, as the spec only allows
+    /// registered environment names on both sides.
+    pub fn matches(&self, name: &str) -> bool {
+        self.identifiers.iter().any(|id| id == name)
+    }
+
+    /// The environment identifiers in session priority order.
+    pub fn identifiers(&self) -> &[String] {
+        &self.identifiers
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2021 System76 <info@system76.com>
+// SPDX-License-Identifier: MPL-2.0
+
+use std::path::Path;
+use thiserror::Error;
+
+/// Errors that can occur while launching a [`DesktopEntry`](crate::DesktopEntry).
+#[derive(Debug, Error)]
+pub enum ExecError<'a> {
+    #[error("Exec key not found for action `{action}` in desktop entry {desktop_entry:?}")]
+    ActionExecKeyNotFound { action: String, desktop_entry: &'a Path },
+
+    #[error("Action `{action}` not found in desktop entry {desktop_entry:?}")]
+    ActionNotFound { action: String, desktop_entry: &'a Path },
+
+    #[error("Exec key not found in desktop entry {0:?}")]
+    MissingExecKey(&'a Path),
+
+    #[error("The Exec string is empty")]
+    EmptyExecString,
+
+    #[error("Executable `{0}` was not found or is not runnable")]
+    ExecutableNotFound(String),
+
+    #[error("Desktop entry {0:?} is not meant to be shown in the current desktop environment")]
+    NotShownInEnvironment(&'a Path),
+
+    #[error("Malformed Exec string: {0}")]
+    InvalidExec(String),
+
+    #[error("Deprecated field code `{0}`")]
+    DeprecatedFieldCode(String),
+
+    #[error("Unknown field code `{0}`")]
+    UnknownFieldCode(String),
+
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
@@ -3,35 +3,93 @@
 
 use crate::exec::error::ExecError;
 use crate::DesktopEntry;
-use std::convert::TryFrom;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
 
+pub mod environment;
 pub mod error;
+pub mod sandbox;
 
-impl DesktopEntry<'_> {
-    /// Launch the given desktop entry action.
-    pub fn launch_action(&self, action: &str, uris: &[&str]) -> Result<(), ExecError> {
-        let has_action = self
-            .actions()
-            .map_or(false,
-                |actions|
-                actions
-                    .split(';')
-                    .any(|act| act == action)
-            );
-        if !has_action {
-            return Err(ExecError::ActionNotFound { action: action.to_string(), desktop_entry: self.path });
+use environment::DesktopEnvironment;
+
+impl<'e> DesktopEntry<'e> {
+    /// Launch the given desktop entry action, returning the spawned child so
+    /// callers can track or wait on it.
+    pub fn launch_action(&self, action: &str, uris: &[&str]) -> Result<Child, ExecError<'e>> {
+        self.launcher().launch_action(action, uris)
+    }
+
+    /// Launch the given desktop entry, returning the spawned child so callers
+    /// can track or wait on it.
+    pub fn launch(&self, uris: &[&str]) -> Result<Child, ExecError<'e>> {
+        self.launcher().launch(uris)
+    }
+
+    /// Launch the entry only if it is meant to run in the current desktop
+    /// environment.
+    ///
+    /// Returns [`ExecError::NotShownInEnvironment`] when the entry is hidden or
+    /// its `OnlyShowIn`/`NotShowIn` keys exclude the running session, so menu
+    /// generators can filter entries instead of hardcoding assumptions.
+    pub fn launch_checked(&self, uris: &[&str]) -> Result<Child, ExecError<'e>> {
+        if self.is_hidden() || !self.shows_in(&DesktopEnvironment::detect()) {
+            return Err(ExecError::NotShownInEnvironment(self.path));
+        }
+        self.launch(uris)
+    }
+
+    /// Whether the entry should be displayed in the current desktop
+    /// environment's menus.
+    ///
+    /// Evaluates the `Hidden` and `NoDisplay` flags together with the
+    /// `OnlyShowIn`/`NotShowIn` keys against the detected
+    /// [`DesktopEnvironment`].
+    pub fn should_show(&self) -> bool {
+        !self.is_hidden() && !self.is_no_display() && self.shows_in(&DesktopEnvironment::detect())
+    }
+
+    // Evaluate `OnlyShowIn`/`NotShowIn` against the given environment. An entry
+    // with `OnlyShowIn` shows only in the listed environments; one with
+    // `NotShowIn` shows everywhere except the listed ones.
+    fn shows_in(&self, environment: &DesktopEnvironment) -> bool {
+        if let Some(only) = self.desktop_entry("OnlyShowIn") {
+            return only.split(';').filter(|e| !e.is_empty()).any(|e| environment.matches(e));
+        }
+        if let Some(not) = self.desktop_entry("NotShowIn") {
+            return !not.split(';').filter(|e| !e.is_empty()).any(|e| environment.matches(e));
         }
-        self.shell_launch(uris, Some(action.to_string()))
+        true
+    }
+
+    // The `Hidden` key marks an entry as deleted; it must never be shown or
+    // launched.
+    fn is_hidden(&self) -> bool {
+        self.desktop_entry("Hidden") == Some("true")
     }
 
-    /// Launch the given desktop entry.
-    pub fn launch(&self, uris: &[&str]) -> Result<(), ExecError> {
-        self.shell_launch(uris, None)
+    // The `NoDisplay` key hides an entry from menus while leaving it valid to
+    // launch directly.
+    fn is_no_display(&self) -> bool {
+        self.desktop_entry("NoDisplay") == Some("true")
     }
 
-    fn shell_launch(&self, uris: &[&str], action: Option<String>) -> Result<(), ExecError> {
+    /// Start building a launch with non-default options, e.g. to override
+    /// whether the child's environment is scrubbed of sandbox-local values.
+    pub fn launcher(&self) -> Launcher<'_, 'e> {
+        Launcher {
+            entry: self,
+            sanitize_environment: sandbox::Sandbox::detect().is_some(),
+            terminal: None,
+        }
+    }
+
+    fn shell_launch(
+        &self,
+        uris: &[&str],
+        action: Option<String>,
+        sanitize_environment: bool,
+        terminal: Option<&str>,
+    ) -> Result<Child, ExecError<'e>> {
         let exec = if let Some(action) = action {
             self.action_exec(&action)
                 .ok_or(ExecError::ActionExecKeyNotFound { action, desktop_entry: self.path })
@@ -40,25 +98,35 @@ impl DesktopEntry<'_> {
                 .ok_or(ExecError::MissingExecKey(self.path))
         }?;
 
-        let exec_args =
-            exec.split_ascii_whitespace()
-                .map(ArgOrFieldCode::try_from)
-                .collect::<Result<Vec<ArgOrFieldCode>, _>>()?;
-
-        let mut exec_args = self.get_args(uris, exec_args);
+        let tokens = unquote(exec)?;
+        let mut exec_args = self.expand_field_codes(&tokens, uris)?;
 
         if exec_args.is_empty() {
             return Err(ExecError::EmptyExecString);
         }
 
+        // Honor `TryExec`: when set, its binary must be runnable before we try
+        // to spawn the real `Exec` command.
+        if let Some(try_exec) = self.desktop_entry("TryExec") {
+            if which(try_exec).is_none() {
+                return Err(ExecError::ExecutableNotFound(try_exec.to_string()));
+            }
+        }
+
         let exec; // trick to keep terminal.to_string_lossy() in scope
         let (exec, args) = if self.terminal() {
-            let (terminal, separator) = detect_terminal();
+            let (emulator, separator) = detect_terminal(terminal);
             exec_args.insert(0, separator.to_owned());
-            exec = terminal.to_string_lossy().to_string();
+            exec = emulator.to_string_lossy().to_string();
             (&exec, &exec_args[..])
         } else {
-            (&exec_args[0], &exec_args[1..])
+            // Resolve the program against PATH so the spawn uses an absolute
+            // path and a missing binary yields a clear error rather than an
+            // opaque `IoError`.
+            let resolved =
+                which(&exec_args[0]).ok_or_else(|| ExecError::ExecutableNotFound(exec_args[0].clone()))?;
+            exec = resolved.to_string_lossy().to_string();
+            (&exec, &exec_args[1..])
         };
 
         let mut cmd = Command::new(exec);
@@ -66,92 +134,312 @@ impl DesktopEntry<'_> {
         if let Some(ref dir) = self.path() {
             cmd.current_dir(dir.as_ref());
         }
-        cmd.args(args).spawn().map(|_| ()).map_err(ExecError::IoError)
+
+        if sanitize_environment {
+            for (key, value) in sandbox::sanitized_environment() {
+                match value {
+                    Some(value) => cmd.env(key, value),
+                    None => cmd.env_remove(key),
+                };
+            }
+        }
+
+        // When the entry opts into startup notification, hand the child a
+        // `DESKTOP_STARTUP_ID` so the compositor can track launch feedback.
+        if self.desktop_entry("StartupNotify") == Some("true") {
+            cmd.env("DESKTOP_STARTUP_ID", startup_id());
+        }
+
+        cmd.args(args).spawn().map_err(ExecError::IoError)
+    }
+
+    // Expand the field codes of each unquoted token into the final argument
+    // list, substituting in place and preserving surrounding literal text.
+    fn expand_field_codes(&self, tokens: &[String], uris: &[&str]) -> Result<Vec<String>, ExecError<'e>> {
+        let mut args = Vec::with_capacity(tokens.len());
+
+        for token in tokens {
+            match token.as_str() {
+                // File/URL lists expand to one argument per entry, but only
+                // when they stand alone.
+                "%F" | "%U" => args.extend(uris.iter().map(ToString::to_string)),
+                // The icon key expands to two arguments when an icon is set.
+                "%i" => {
+                    if let Some(icon) = self.icon() {
+                        args.push("--icon".to_string());
+                        args.push(icon.to_string());
+                    }
+                }
+                _ => {
+                    if let Some(arg) = self.substitute_field_codes(token, uris)? {
+                        args.push(arg);
+                    }
+                }
+            }
+        }
+
+        Ok(args)
     }
 
-    // Replace field code with their values and ignore deprecated and unknown field codes
-    fn get_args(&self, uris: &[&str], exec_args: Vec<ArgOrFieldCode>) -> Vec<String> {
-        exec_args
-            .iter()
-            .filter_map(|arg| match arg {
-                ArgOrFieldCode::SingleFileName | ArgOrFieldCode::SingleUrl => {
-                    uris.first().map(|filename| filename.to_string())
+    // Substitute the in-argument field codes of a single token. Returns `None`
+    // when the token was a bare field code that resolved to nothing, so it can
+    // be dropped rather than passed as an empty argument.
+    fn substitute_field_codes(&self, token: &str, uris: &[&str]) -> Result<Option<String>, ExecError<'e>> {
+        let mut out = String::new();
+        let mut produced = false;
+        let mut chars = token.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                produced = true;
+                continue;
+            }
+
+            match chars.next() {
+                Some('%') => {
+                    out.push('%');
+                    produced = true;
                 }
-                ArgOrFieldCode::FileList | ArgOrFieldCode::UrlList => {
-                    if !uris.is_empty() {
-                        Some(uris.join(" "))
-                    } else {
-                        None
+                Some('f') | Some('u') => {
+                    if let Some(first) = uris.first() {
+                        out.push_str(first);
+                        produced = true;
                     }
                 }
-                ArgOrFieldCode::IconKey => self.icon().map(ToString::to_string),
-                ArgOrFieldCode::TranslatedName => {
-                    let locale = std::env::var("LANG").ok();
-                    if let Some(locale) = locale {
-                        let locale = locale.split_once('.').map(|(locale, _)| locale);
-                        self.name(locale).map(|locale| locale.to_string())
-                    } else {
-                        None
+                Some('c') => {
+                    if let Some(name) = self.translated_name() {
+                        out.push_str(&name);
+                        produced = true;
                     }
                 }
-                ArgOrFieldCode::DesktopFileLocation => Some(self.path.to_string_lossy().to_string()),
-                ArgOrFieldCode::Arg(arg) => Some(arg.to_string()),
-            })
-            .collect()
+                Some('k') => {
+                    out.push_str(&self.path.to_string_lossy());
+                    produced = true;
+                }
+                Some(c @ ('d' | 'D' | 'n' | 'N' | 'v' | 'm')) => {
+                    return Err(ExecError::DeprecatedFieldCode(format!("%{c}")));
+                }
+                // List and icon codes are only valid as standalone tokens.
+                Some(c @ ('F' | 'U' | 'i')) => {
+                    return Err(ExecError::InvalidExec(format!("field code `%{c}` must stand alone")));
+                }
+                Some(other) => return Err(ExecError::UnknownFieldCode(format!("%{other}"))),
+                None => return Err(ExecError::InvalidExec("trailing `%` in Exec string".to_string())),
+            }
+        }
+
+        Ok((produced || !out.is_empty()).then_some(out))
+    }
+
+    /// The absolute path of the binary this entry would run, respecting
+    /// `TryExec` and resolving the `Exec` program against `PATH`.
+    ///
+    /// Returns `None` when the application is not installed, making this a
+    /// cheap "is this app available?" check for menu and launcher frontends.
+    pub fn executable(&self) -> Option<PathBuf> {
+        if let Some(try_exec) = self.desktop_entry("TryExec") {
+            which(try_exec)?;
+        }
+        let exec = self.exec()?;
+        let program = unquote(exec).ok()?.into_iter().next()?;
+        which(&program)
+    }
+
+    // The localized `Name` for the current `LANG`, used by the `%c` field code.
+    fn translated_name(&self) -> Option<String> {
+        let locale = std::env::var("LANG").ok()?;
+        let locale = locale.split_once('.').map(|(locale, _)| locale);
+        self.name(locale).map(|name| name.to_string())
     }
 }
 
-// either a command line argument or a field-code as described
-// in https://specifications.freedesktop.org/desktop-entry-spec/desktop-entry-spec-latest.html#exec-variables
-enum ArgOrFieldCode<'a> {
-    SingleFileName,
-    FileList,
-    SingleUrl,
-    UrlList,
-    IconKey,
-    TranslatedName,
-    DesktopFileLocation,
-    Arg(&'a str),
+/// Builder that launches a [`DesktopEntry`] with non-default options.
+///
+/// Obtained via [`DesktopEntry::launcher`]. It controls whether the child's
+/// environment is scrubbed of sandbox-local values (on by default whenever
+/// dexrs is itself running inside a bundle, see [`sandbox`]) and which terminal
+/// emulator is used for entries with `Terminal=true`.
+pub struct Launcher<'a, 'e> {
+    entry: &'a DesktopEntry<'e>,
+    sanitize_environment: bool,
+    terminal: Option<String>,
 }
 
-impl<'a> TryFrom<&'a str> for ArgOrFieldCode<'a> {
-    type Error = ExecError<'a>;
+impl<'e> Launcher<'_, 'e> {
+    /// Override whether the spawned child's environment is scrubbed of
+    /// sandbox-local path entries before launch.
+    pub fn sanitize_environment(mut self, sanitize: bool) -> Self {
+        self.sanitize_environment = sanitize;
+        self
+    }
+
+    /// Prefer the given terminal emulator for `Terminal=true` entries instead
+    /// of the autodetected one. Overrides the `TERMINAL` environment variable.
+    pub fn terminal(mut self, emulator: impl Into<String>) -> Self {
+        self.terminal = Some(emulator.into());
+        self
+    }
+
+    /// Launch the entry with the configured options.
+    pub fn launch(self, uris: &[&str]) -> Result<Child, ExecError<'e>> {
+        self.entry.shell_launch(uris, None, self.sanitize_environment, self.terminal.as_deref())
+    }
 
-    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
-        match value {
-            "%f" => Ok(ArgOrFieldCode::SingleFileName),
-            "%F" => Ok(ArgOrFieldCode::FileList),
-            "%u" => Ok(ArgOrFieldCode::SingleUrl),
-            "%U" => Ok(ArgOrFieldCode::UrlList),
-            "%i" => Ok(ArgOrFieldCode::IconKey),
-            "%c" => Ok(ArgOrFieldCode::TranslatedName),
-            "%k" => Ok(ArgOrFieldCode::DesktopFileLocation),
-            "%d" | "%D" | "%n" | "%N" | "%v" | "%m" => Err(ExecError::DeprecatedFieldCode(value.to_string())),
-            other if other.starts_with('%') => Err(ExecError::UnknownFieldCode(other.to_string())),
-            other => Ok(ArgOrFieldCode::Arg(other)),
+    /// Launch the given action of the entry with the configured options.
+    pub fn launch_action(self, action: &str, uris: &[&str]) -> Result<Child, ExecError<'e>> {
+        let has_action = self
+            .entry
+            .actions()
+            .map_or(false,
+                |actions|
+                actions
+                    .split(';')
+                    .any(|act| act == action)
+            );
+        if !has_action {
+            return Err(ExecError::ActionNotFound { action: action.to_string(), desktop_entry: self.entry.path });
         }
+        self.entry.shell_launch(uris, Some(action.to_string()), self.sanitize_environment, self.terminal.as_deref())
     }
 }
 
-// Returns the default terminal emulator linked to `/usr/bin/x-terminal-emulator`
-// or fallback to gnome terminal, then konsole
-fn detect_terminal() -> (PathBuf, &'static str) {
+// Unquote an `Exec` string into its tokens following the Desktop Entry spec's
+// quoting rules: double quotes group a token, inside quotes a backslash escapes
+// `"`, `` ` ``, `$` and `\`, and the reserved characters are rejected outside
+// quotes. Field codes are left untouched for the substitution pass.
+// https://specifications.freedesktop.org/desktop-entry-spec/desktop-entry-spec-latest.html#exec-variables
+fn unquote<'e>(exec: &str) -> Result<Vec<String>, ExecError<'e>> {
+    const RESERVED: &[char] = &['`', '$', '<', '>', '~', '|', '&', '*', '?', '#', '(', ')', ';', '\''];
+
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            '"' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(esc @ ('"' | '`' | '$' | '\\')) => current.push(esc),
+                            Some(other) => {
+                                current.push('\\');
+                                current.push(other);
+                            }
+                            None => return Err(ExecError::InvalidExec("unterminated escape in quoted argument".to_string())),
+                        },
+                        Some(other) => current.push(other),
+                        None => return Err(ExecError::InvalidExec("unterminated double quote".to_string())),
+                    }
+                }
+            }
+            '\\' => return Err(ExecError::InvalidExec("backslash outside quotes must be quoted".to_string())),
+            c if RESERVED.contains(&c) => {
+                return Err(ExecError::InvalidExec(format!("reserved character `{c}` must be quoted")));
+            }
+            c => {
+                in_token = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_token {
+        tokens.push(current);
+    }
+
+    Ok(tokens)
+}
+
+// Resolve a program to an absolute path. A value containing `/` is treated as
+// a path and checked directly; a bare name is looked up across the `PATH`
+// entries. Returns the first runnable match, or `None` if none is executable.
+fn which(program: &str) -> Option<PathBuf> {
+    if program.contains('/') {
+        let path = Path::new(program);
+        return is_executable(path).then(|| path.to_path_buf());
+    }
+
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|candidate| is_executable(candidate))
+}
+
+// Whether `path` is a regular file with at least one execute bit set.
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::metadata(path).map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0).unwrap_or(false)
+}
+
+// Known terminal emulators and the argument that introduces the command to run
+// inside them. Ordered by preference for the autodetection fallback.
+const KNOWN_TERMINALS: &[(&str, &str)] = &[
+    ("alacritty", "-e"),
+    ("kitty", "-e"),
+    ("foot", "-e"),
+    ("wezterm", "-e"),
+    ("gnome-terminal", "--"),
+    ("tilix", "--"),
+    ("konsole", "-e"),
+    ("xterm", "-e"),
+];
+
+// Resolve the terminal emulator to use for `Terminal=true` entries and the
+// argument separator it expects before the command. A `preference` (from the
+// caller or `$TERMINAL`) wins, then the `x-terminal-emulator` symlink, then the
+// first known emulator found on `PATH`.
+fn detect_terminal(preference: Option<&str>) -> (PathBuf, &'static str) {
     use std::fs::read_link;
 
     const SYMLINK: &str = "/usr/bin/x-terminal-emulator";
 
-    if let Ok(found) = read_link(SYMLINK) {
-        let arg = if found.to_string_lossy().contains("gnome-terminal") { "--" } else { "-e" };
+    let preference = preference.map(ToOwned::to_owned).or_else(|| std::env::var("TERMINAL").ok());
+    if let Some(name) = preference {
+        if let Some(path) = which(&name) {
+            return (path, terminal_separator(&name));
+        }
+    }
 
-        return (read_link(&found).unwrap_or(found), arg);
+    if let Ok(found) = read_link(SYMLINK) {
+        let resolved = read_link(&found).unwrap_or(found);
+        let separator = terminal_separator(&resolved.to_string_lossy());
+        return (resolved, separator);
     }
 
-    let gnome_terminal = PathBuf::from("/usr/bin/gnome-terminal");
-    if gnome_terminal.exists() {
-        (gnome_terminal, "--")
-    } else {
-        (PathBuf::from("/usr/bin/konsole"), "-e")
+    for &(name, separator) in KNOWN_TERMINALS {
+        if let Some(path) = which(name) {
+            return (path, separator);
+        }
     }
+
+    (PathBuf::from("/usr/bin/xterm"), "-e")
+}
+
+// The command separator expected by `program`, looked up by file name in
+// [`KNOWN_TERMINALS`] and defaulting to `-e` for unknown emulators.
+fn terminal_separator(program: &str) -> &'static str {
+    let name = Path::new(program).file_name().and_then(|name| name.to_str()).unwrap_or(program);
+    KNOWN_TERMINALS.iter().find(|&&(terminal, _)| terminal == name).map_or("-e", |&(_, separator)| separator)
+}
+
+// Generate a `DESKTOP_STARTUP_ID` for a child that requested startup
+// notification, following the common `<id>_TIME<millis>` convention.
+fn startup_id() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    format!("dexrs-{}_TIME{}", std::process::id(), millis)
 }
 
 #[cfg(test)]
@@ -216,6 +504,91 @@ mod test {
         assert_that!(result).is_ok();
     }
 
+    #[test]
+    fn should_unquote_grouped_arguments() {
+        let tokens = super::unquote(r#"sh -c "bar baz""#).unwrap();
+        assert_that!(tokens).is_equal_to(vec!["sh".to_string(), "-c".to_string(), "bar baz".to_string()]);
+    }
+
+    #[test]
+    fn should_reject_unquoted_reserved_characters() {
+        let result = super::unquote("foo $bar");
+        assert_that!(result).is_err().matches(|err| matches!(err, ExecError::InvalidExec(_)));
+    }
+
+    #[test]
+    fn should_substitute_field_codes_in_place() {
+        let path = PathBuf::from("tests/entries/empty-exec.desktop");
+        let input = fs::read_to_string(&path).unwrap();
+        let de = DesktopEntry::decode(path.as_path(), &input).unwrap();
+
+        let tokens = super::unquote("foo --file=%f").unwrap();
+        let args = de.expand_field_codes(&tokens, &["/tmp/a.txt"]).unwrap();
+        assert_that!(args).is_equal_to(vec!["foo".to_string(), "--file=/tmp/a.txt".to_string()]);
+    }
+
+    #[test]
+    fn should_collapse_escaped_percent() {
+        let path = PathBuf::from("tests/entries/empty-exec.desktop");
+        let input = fs::read_to_string(&path).unwrap();
+        let de = DesktopEntry::decode(path.as_path(), &input).unwrap();
+
+        let tokens = super::unquote("foo 50%%").unwrap();
+        let args = de.expand_field_codes(&tokens, &[]).unwrap();
+        assert_that!(args).is_equal_to(vec!["foo".to_string(), "50%".to_string()]);
+    }
+
+    #[test]
+    fn should_honor_only_show_in() {
+        use super::environment::DesktopEnvironment;
+
+        let input = "[Desktop Entry]\nType=Application\nName=Foo\nExec=foo\nOnlyShowIn=KDE;\n";
+        let de = DesktopEntry::decode(Path::new("foo.desktop"), input).unwrap();
+
+        assert_that!(de.shows_in(&DesktopEnvironment::from_list("GNOME"))).is_false();
+        assert_that!(de.shows_in(&DesktopEnvironment::from_list("ubuntu:KDE"))).is_true();
+    }
+
+    #[test]
+    fn should_honor_not_show_in() {
+        use super::environment::DesktopEnvironment;
+
+        let input = "[Desktop Entry]\nType=Application\nName=Foo\nExec=foo\nNotShowIn=GNOME;\n";
+        let de = DesktopEntry::decode(Path::new("foo.desktop"), input).unwrap();
+
+        assert_that!(de.shows_in(&DesktopEnvironment::from_list("GNOME"))).is_false();
+        assert_that!(de.shows_in(&DesktopEnvironment::from_list("KDE"))).is_true();
+    }
+
+    #[test]
+    fn hidden_entry_is_never_shown() {
+        let input = "[Desktop Entry]\nType=Application\nName=Foo\nExec=foo\nHidden=true\n";
+        let de = DesktopEntry::decode(Path::new("foo.desktop"), input).unwrap();
+
+        assert_that!(de.should_show()).is_false();
+        assert_that!(de.launch_checked(&[]))
+            .is_err()
+            .matches(|err| matches!(err, ExecError::NotShownInEnvironment(_)));
+    }
+
+    #[test]
+    fn should_resolve_absolute_executable() {
+        let exe = std::env::current_exe().unwrap();
+        assert_that!(super::which(&exe.to_string_lossy())).is_some();
+    }
+
+    #[test]
+    fn should_not_resolve_missing_executable() {
+        assert_that!(super::which("dexrs-definitely-not-installed-xyz")).is_none();
+    }
+
+    #[test]
+    fn terminal_separator_is_looked_up_by_name() {
+        assert_that!(super::terminal_separator("/usr/bin/gnome-terminal")).is_equal_to("--");
+        assert_that!(super::terminal_separator("alacritty")).is_equal_to("-e");
+        assert_that!(super::terminal_separator("some-unknown-term")).is_equal_to("-e");
+    }
+
     #[test]
     #[ignore = "Needs a desktop environment with alacritty installed, run locally only"]
     fn should_launch_action() {
@@ -0,0 +1,175 @@
+// Copyright 2021 System76 <info@system76.com>
+// SPDX-License-Identifier: MPL-2.0
+
+//! Detection of AppImage/Flatpak/Snap bundles and neutralization of the
+//! sandbox-local environment they leak into child processes.
+//!
+//! When dexrs itself runs inside a bundle the process environment is polluted
+//! with values (`LD_LIBRARY_PATH`, `GST_PLUGIN_SYSTEM_PATH`, `GTK_PATH`, …)
+//! that point back under the bundle root. Launching an unrelated desktop
+//! application with that environment inherited makes it pick up the wrong
+//! shared libraries. [`sanitized_environment`] produces the set of overrides
+//! that strip those entries back out before the child is spawned.
+
+use std::path::{Path, PathBuf};
+
+/// Path-list environment variables that a bundle typically rewrites to point
+/// under its own root, in the order we normalize them.
+const PATH_LIST_VARS: &[&str] = &[
+    "PATH",
+    "LD_LIBRARY_PATH",
+    "XDG_DATA_DIRS",
+    "GST_PLUGIN_SYSTEM_PATH",
+    "GST_PLUGIN_PATH",
+    "GTK_PATH",
+    "GIO_MODULE_DIR",
+    "GSETTINGS_SCHEMA_DIR",
+    "PYTHONPATH",
+    "PERLLIB",
+];
+
+/// The kind of application bundle dexrs is currently running inside, together
+/// with the filesystem root whose entries must be scrubbed from the child
+/// environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sandbox {
+    /// An AppImage, rooted at `$APPDIR`.
+    AppImage(PathBuf),
+    /// A Flatpak sandbox, rooted at `/app`.
+    Flatpak,
+    /// A Snap, rooted at `$SNAP`.
+    Snap(PathBuf),
+}
+
+/// Whether dexrs is running from an AppImage, detected via `$APPDIR`.
+pub fn is_appimage() -> bool {
+    std::env::var_os("APPDIR").is_some()
+}
+
+/// Whether dexrs is running inside a Flatpak sandbox, detected via
+/// `$FLATPAK_ID` or the `/.flatpak-info` marker file.
+pub fn is_flatpak() -> bool {
+    std::env::var_os("FLATPAK_ID").is_some() || Path::new("/.flatpak-info").exists()
+}
+
+/// Whether dexrs is running inside a Snap, detected via `$SNAP`.
+pub fn is_snap() -> bool {
+    std::env::var_os("SNAP").is_some()
+}
+
+impl Sandbox {
+    /// Detect the bundle dexrs is running inside, if any.
+    pub fn detect() -> Option<Self> {
+        if is_appimage() {
+            std::env::var_os("APPDIR").map(|dir| Sandbox::AppImage(PathBuf::from(dir)))
+        } else if is_flatpak() {
+            Some(Sandbox::Flatpak)
+        } else if is_snap() {
+            std::env::var_os("SNAP").map(|dir| Sandbox::Snap(PathBuf::from(dir)))
+        } else {
+            None
+        }
+    }
+
+    /// The filesystem root whose entries are sandbox-local and must not be
+    /// forwarded to launched applications.
+    pub fn root(&self) -> &Path {
+        match self {
+            Sandbox::AppImage(dir) | Sandbox::Snap(dir) => dir,
+            Sandbox::Flatpak => Path::new("/app"),
+        }
+    }
+}
+
+/// Normalize a single colon-separated path list relative to `root`.
+///
+/// Splits on `:`, drops empty components, drops any entry whose canonicalized
+/// path lies under `root`, and de-duplicates while preserving the *last*
+/// (lowest-priority) occurrence of a repeated entry. Returns `None` when
+/// nothing survives, signalling that the variable should be unset rather than
+/// exported empty.
+pub fn normalize_pathlist(value: &str, root: &Path) -> Option<String> {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+
+    // Walk from the back so that keeping the first occurrence we *see* keeps
+    // the last occurrence in the original order.
+    let mut kept = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for entry in value.split(':').rev() {
+        if entry.is_empty() {
+            continue;
+        }
+        let canonical = Path::new(entry)
+            .canonicalize()
+            .unwrap_or_else(|_| PathBuf::from(entry));
+        if canonical.starts_with(&root) {
+            continue;
+        }
+        if seen.insert(canonical) {
+            kept.push(entry);
+        }
+    }
+
+    if kept.is_empty() {
+        None
+    } else {
+        kept.reverse();
+        Some(kept.join(":"))
+    }
+}
+
+/// Compute the environment overrides that neutralize the current sandbox.
+///
+/// Each entry is a `(name, value)` pair: `Some(value)` assigns the variable on
+/// the child and `None` unsets it. Where the bundle saved a pre-sandbox value
+/// under the AppImage-style `*_ORIG` convention that snapshot is restored
+/// verbatim; otherwise the live value is passed through [`normalize_pathlist`].
+/// Returns an empty vector when no sandbox is detected.
+pub fn sanitized_environment() -> Vec<(&'static str, Option<String>)> {
+    match Sandbox::detect() {
+        Some(sandbox) => sanitized_environment_for(&sandbox),
+        None => Vec::new(),
+    }
+}
+
+fn sanitized_environment_for(sandbox: &Sandbox) -> Vec<(&'static str, Option<String>)> {
+    let root = sandbox.root();
+
+    PATH_LIST_VARS
+        .iter()
+        .filter_map(|&name| {
+            if let Some(orig) = std::env::var_os(format!("{name}_ORIG")) {
+                let orig = orig.to_string_lossy().into_owned();
+                return Some((name, (!orig.is_empty()).then_some(orig)));
+            }
+
+            let current = std::env::var(name).ok()?;
+            Some((name, normalize_pathlist(&current, root)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::normalize_pathlist;
+    use speculoos::prelude::*;
+    use std::path::Path;
+
+    #[test]
+    fn should_drop_entries_under_the_sandbox_root() {
+        let result = normalize_pathlist("/opt/app/lib:/usr/lib:/opt/app/extra", Path::new("/opt/app"));
+        assert_that!(result).is_some().is_equal_to("/usr/lib".to_string());
+    }
+
+    #[test]
+    fn should_dedupe_keeping_the_last_occurrence() {
+        let result = normalize_pathlist("/usr/lib:/usr/local/lib:/usr/lib", Path::new("/opt/app"));
+        assert_that!(result).is_some().is_equal_to("/usr/local/lib:/usr/lib".to_string());
+    }
+
+    #[test]
+    fn should_unset_when_nothing_survives() {
+        let result = normalize_pathlist("/opt/app/lib::", Path::new("/opt/app"));
+        assert_that!(result).is_none();
+    }
+}
@@ -0,0 +1,348 @@
+// Copyright 2021 System76 <info@system76.com>
+// SPDX-License-Identifier: MPL-2.0
+
+//! Resolve which installed applications can open a given file or MIME type.
+//!
+//! [`AppRegistry::load`] scans the `applications` directories under
+//! `$XDG_DATA_HOME` and `$XDG_DATA_DIRS`, parses every `.desktop` file and
+//! indexes its `MimeType=` associations. Default/added/removed associations
+//! from `mimeapps.list` are layered on top following the order documented in
+//! the [Association between MIME types and applications] spec.
+//!
+//! [Association between MIME types and applications]:
+//!     https://specifications.freedesktop.org/mime-apps-spec/latest/
+
+use crate::exec::error::ExecError;
+use crate::DesktopEntry;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// The canonical identifier of an installed desktop entry, i.e. its path
+/// relative to an `applications` directory with `/` folded to `-`
+/// (`org.gnome.gedit.desktop`, `kde-konsole.desktop`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DesktopEntryId(String);
+
+impl DesktopEntryId {
+    /// The identifier as it appears in `mimeapps.list`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A `.desktop` file discovered on disk, kept with the raw contents so it can
+/// be decoded into a borrowing [`DesktopEntry`] on demand.
+struct InstalledApp {
+    path: PathBuf,
+    contents: String,
+}
+
+/// An error returned while opening a path or URI through the registry.
+#[derive(Debug)]
+pub enum OpenError<'a> {
+    /// The MIME type of the argument could not be determined.
+    UnknownMimeType(String),
+    /// No installed application is associated with the MIME type.
+    NoHandler(String),
+    /// The selected handler failed to launch.
+    Exec(ExecError<'a>),
+}
+
+/// An index of installed applications keyed by the MIME types they handle.
+pub struct AppRegistry {
+    apps: HashMap<DesktopEntryId, InstalledApp>,
+    /// MIME type -> applications declaring it in their `MimeType=` list.
+    mime_index: HashMap<String, Vec<DesktopEntryId>>,
+    associations: Associations,
+}
+
+impl AppRegistry {
+    /// Scan the XDG application directories and build the registry.
+    pub fn load() -> Self {
+        let mut apps = HashMap::new();
+        let mut mime_index: HashMap<String, Vec<DesktopEntryId>> = HashMap::new();
+
+        // Lowest priority first so higher-priority directories overwrite the
+        // same id, matching XDG lookup order.
+        for dir in application_dirs().into_iter().rev() {
+            scan_dir(&dir, &dir, &mut apps);
+        }
+
+        for (id, app) in &apps {
+            if let Some(entry) = decode(app) {
+                if let Some(mimes) = entry.mime_type() {
+                    for mime in mimes.split(';').filter(|m| !m.is_empty()) {
+                        mime_index
+                            .entry(mime.to_string())
+                            .or_default()
+                            .push(id.clone());
+                    }
+                }
+            }
+        }
+
+        AppRegistry {
+            apps,
+            mime_index,
+            associations: Associations::load(),
+        }
+    }
+
+    /// Every application able to open `mime`, defaults first.
+    ///
+    /// The result merges the `mimeapps.list` associations — `[Default
+    /// Applications]`, then `[Added Associations]`, minus `[Removed
+    /// Associations]` — with the scanned `MimeType` index.
+    pub fn open_with_candidates(&self, mime: &str) -> Vec<DesktopEntry<'_>> {
+        let mut ordered: Vec<DesktopEntryId> = Vec::new();
+        let mut push = |ordered: &mut Vec<DesktopEntryId>, id: &DesktopEntryId| {
+            if self.associations.is_removed(mime, id) {
+                return;
+            }
+            if !ordered.contains(id) && self.apps.contains_key(id) {
+                ordered.push(id.clone());
+            }
+        };
+
+        for id in self.associations.defaults(mime) {
+            push(&mut ordered, id);
+        }
+        for id in self.associations.added(mime) {
+            push(&mut ordered, id);
+        }
+        if let Some(ids) = self.mime_index.get(mime) {
+            for id in ids {
+                push(&mut ordered, id);
+            }
+        }
+
+        ordered
+            .iter()
+            .filter_map(|id| self.apps.get(id).and_then(decode))
+            .collect()
+    }
+
+    /// The default application for `mime`, if one is associated.
+    pub fn default_for(&self, mime: &str) -> Option<DesktopEntry<'_>> {
+        self.open_with_candidates(mime).into_iter().next()
+    }
+
+    /// Guess the MIME type of `path_or_uri`, pick its default handler and
+    /// launch it with the argument as a single URI.
+    pub fn open<'a>(&'a self, path_or_uri: &str) -> Result<(), OpenError<'a>> {
+        let mime = guess_mime_type(path_or_uri)
+            .ok_or_else(|| OpenError::UnknownMimeType(path_or_uri.to_string()))?;
+
+        let handler = self
+            .default_for(&mime)
+            .ok_or_else(|| OpenError::NoHandler(mime.clone()))?;
+
+        handler.launch(&[path_or_uri]).map(|_| ()).map_err(OpenError::Exec)
+    }
+}
+
+/// Decode an installed app into a borrowing [`DesktopEntry`], discarding
+/// entries that fail to parse.
+fn decode(app: &InstalledApp) -> Option<DesktopEntry<'_>> {
+    DesktopEntry::decode(&app.path, &app.contents).ok()
+}
+
+/// The `applications` directories to scan, highest priority first.
+fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(data_home) = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    {
+        dirs.push(data_home.join("applications"));
+    }
+
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+    for dir in data_dirs.split(':').filter(|d| !d.is_empty()) {
+        dirs.push(Path::new(dir).join("applications"));
+    }
+
+    dirs
+}
+
+/// Recursively collect `.desktop` files under `dir`, deriving each id relative
+/// to the `applications` `root`.
+fn scan_dir(root: &Path, dir: &Path, apps: &mut HashMap<DesktopEntryId, InstalledApp>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            scan_dir(root, &path, apps);
+        } else if path.extension().is_some_and(|ext| ext == "desktop") {
+            let id = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('/', "-");
+            if let Ok(contents) = std::fs::read_to_string(&path) {
+                apps.insert(DesktopEntryId(id), InstalledApp { path, contents });
+            }
+        }
+    }
+}
+
+/// Guess the MIME type of a path or `file://` URI via `xdg-mime`.
+fn guess_mime_type(path_or_uri: &str) -> Option<String> {
+    let path = path_or_uri.strip_prefix("file://").unwrap_or(path_or_uri);
+
+    let output = std::process::Command::new("xdg-mime")
+        .args(["query", "filetype", path])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let mime = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!mime.is_empty()).then_some(mime)
+}
+
+/// Associations parsed from the `mimeapps.list` search path.
+#[derive(Default)]
+struct Associations {
+    default: HashMap<String, Vec<DesktopEntryId>>,
+    added: HashMap<String, Vec<DesktopEntryId>>,
+    removed: HashMap<String, Vec<DesktopEntryId>>,
+}
+
+impl Associations {
+    /// Parse every `mimeapps.list` in the search path, highest priority first.
+    fn load() -> Self {
+        let mut associations = Associations::default();
+        for path in mimeapps_list_paths() {
+            if let Ok(contents) = std::fs::read_to_string(&path) {
+                associations.merge(&contents);
+            }
+        }
+        associations
+    }
+
+    /// Merge one `mimeapps.list`, processed highest priority first.
+    ///
+    /// `[Default Applications]` is first-wins: a default already recorded for a
+    /// MIME type is not overwritten by a lower-priority file. `[Added
+    /// Associations]` and `[Removed Associations]` are instead unioned across
+    /// the config chain, preserving order and discarding duplicates, as the
+    /// spec requires.
+    fn merge(&mut self, contents: &str) {
+        let mut section = None;
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = Some(name.to_string());
+                continue;
+            }
+
+            let Some((mime, ids)) = line.split_once('=') else {
+                continue;
+            };
+            let mime = mime.trim().to_string();
+            let ids = ids
+                .split(';')
+                .filter(|id| !id.is_empty())
+                .map(|id| DesktopEntryId(id.trim().to_string()));
+
+            match section.as_deref() {
+                Some("Default Applications") => {
+                    self.default.entry(mime).or_insert_with(|| ids.collect());
+                }
+                Some("Added Associations") => extend_unique(&mut self.added, mime, ids),
+                Some("Removed Associations") => extend_unique(&mut self.removed, mime, ids),
+                _ => continue,
+            }
+        }
+    }
+
+    fn defaults(&self, mime: &str) -> &[DesktopEntryId] {
+        self.default.get(mime).map_or(&[], Vec::as_slice)
+    }
+
+    fn added(&self, mime: &str) -> &[DesktopEntryId] {
+        self.added.get(mime).map_or(&[], Vec::as_slice)
+    }
+
+    fn is_removed(&self, mime: &str, id: &DesktopEntryId) -> bool {
+        self.removed.get(mime).is_some_and(|ids| ids.contains(id))
+    }
+}
+
+/// Append `ids` to the list for `mime`, preserving order and skipping ids
+/// already present so associations union across the config chain.
+fn extend_unique(
+    map: &mut HashMap<String, Vec<DesktopEntryId>>,
+    mime: String,
+    ids: impl Iterator<Item = DesktopEntryId>,
+) {
+    let entry = map.entry(mime).or_default();
+    for id in ids {
+        if !entry.contains(&id) {
+            entry.push(id);
+        }
+    }
+}
+
+/// The `mimeapps.list` files to consult, highest priority first.
+///
+/// Follows the spec's search order: the config home, then each
+/// `$XDG_CONFIG_DIRS` entry (e.g. `/etc/xdg` for system-wide admin defaults),
+/// then the data `applications` directories. Within every tier the
+/// `$desktop`-prefixed variants from `XDG_CURRENT_DESKTOP` take precedence over
+/// the plain `mimeapps.list`.
+fn mimeapps_list_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let prefixes = desktop_prefixes();
+
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+    if let Some(config_home) = config_home {
+        push_mimeapps_list(&mut paths, &config_home, &prefixes);
+    }
+
+    let config_dirs = std::env::var("XDG_CONFIG_DIRS").unwrap_or_else(|_| "/etc/xdg".to_string());
+    for dir in config_dirs.split(':').filter(|d| !d.is_empty()) {
+        push_mimeapps_list(&mut paths, Path::new(dir), &prefixes);
+    }
+
+    for dir in application_dirs() {
+        push_mimeapps_list(&mut paths, &dir, &prefixes);
+    }
+
+    paths
+}
+
+/// The lowercased `XDG_CURRENT_DESKTOP` components used to build the
+/// `$desktop-mimeapps.list` variants, highest priority first.
+fn desktop_prefixes() -> Vec<String> {
+    std::env::var("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .split(':')
+        .filter(|d| !d.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect()
+}
+
+/// Push the `$desktop`-prefixed `mimeapps.list` variants for `dir` followed by
+/// the plain file, in priority order.
+fn push_mimeapps_list(paths: &mut Vec<PathBuf>, dir: &Path, prefixes: &[String]) {
+    for prefix in prefixes {
+        paths.push(dir.join(format!("{prefix}-mimeapps.list")));
+    }
+    paths.push(dir.join("mimeapps.list"));
+}